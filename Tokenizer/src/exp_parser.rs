@@ -1,67 +1,285 @@
+use crate::tokenizer::{Token, TokenKind};
+use crate::unescape::unescape;
 use std::collections::HashMap;
+use std::fmt;
 
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
 pub enum Value {
     Int(i32),
+    Float(f64),
+    Bool(bool),
     Str(String),
 }
 
+#[derive(Debug)]
+pub struct ParseError {
+    pub message: String,
+    pub line: u16,
+    pub col: u16,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.col, self.message)
+    }
+}
+
+fn strip_base_prefix<'a>(text: &'a str, lower: &str, upper: &str) -> Option<&'a str> {
+    text.strip_prefix(lower).or_else(|| text.strip_prefix(upper))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Int(_) => "int",
+        Value::Float(_) => "float",
+        Value::Bool(_) => "bool",
+        Value::Str(_) => "string",
+    }
+}
+
+fn as_f64(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Int(n) => Ok(*n as f64),
+        Value::Float(f) => Ok(*f),
+        other => Err(format!("Expected a number, found a {}", type_name(other))),
+    }
+}
+
+fn as_int(value: &Value) -> Result<i32, String> {
+    match value {
+        Value::Int(n) => Ok(*n),
+        other => Err(format!("Expected an integer, found a {}", type_name(other))),
+    }
+}
+
+/// Applies a binary arithmetic operator, promoting `Int` to `Float` when
+/// either operand is already a float. `int_op` is one of the `checked_*`
+/// functions so an overflowing `Int` op produces a descriptive error
+/// instead of panicking.
+fn numeric_binop(
+    a: Value,
+    b: Value,
+    op_name: &str,
+    int_op: impl Fn(i32, i32) -> Option<i32>,
+    float_op: impl Fn(f64, f64) -> f64,
+) -> Result<Value, String> {
+    match (&a, &b) {
+        (Value::Int(x), Value::Int(y)) => int_op(*x, *y)
+            .map(Value::Int)
+            .ok_or_else(|| format!("Integer overflow in ({}) operation", op_name)),
+        (Value::Int(_) | Value::Float(_), Value::Int(_) | Value::Float(_)) => {
+            Ok(Value::Float(float_op(as_f64(&a)?, as_f64(&b)?)))
+        }
+        _ => Err(format!(
+            "Cannot apply ({}) to a {} and a {}",
+            op_name,
+            type_name(&a),
+            type_name(&b)
+        )),
+    }
+}
+
+fn divide(a: Value, b: Value) -> Result<Value, String> {
+    if let (Value::Int(x), Value::Int(y)) = (&a, &b) {
+        return x
+            .checked_div(*y)
+            .map(Value::Int)
+            .ok_or_else(|| "Division by zero or overflow".to_string());
+    }
+    Ok(Value::Float(as_f64(&a)? / as_f64(&b)?))
+}
+
+fn modulo(a: Value, b: Value) -> Result<Value, String> {
+    if let (Value::Int(x), Value::Int(y)) = (&a, &b) {
+        return x
+            .checked_rem(*y)
+            .map(Value::Int)
+            .ok_or_else(|| "Modulo by zero or overflow".to_string());
+    }
+    Ok(Value::Float(as_f64(&a)? % as_f64(&b)?))
+}
+
+fn negate(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Int(n) => Ok(Value::Int(-n)),
+        Value::Float(f) => Ok(Value::Float(-f)),
+        other => Err(format!("Cannot negate a {}", type_name(&other))),
+    }
+}
+
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+fn compare(a: Value, b: Value, op: CompareOp) -> Result<Value, String> {
+    let (x, y) = (as_f64(&a), as_f64(&b));
+    let (x, y) = match (x, y) {
+        (Ok(x), Ok(y)) => (x, y),
+        _ => {
+            return Err(format!(
+                "Cannot compare a {} and a {}",
+                type_name(&a),
+                type_name(&b)
+            ))
+        }
+    };
+    let result = match op {
+        CompareOp::Eq => x == y,
+        CompareOp::Ne => x != y,
+        CompareOp::Lt => x < y,
+        CompareOp::Le => x <= y,
+        CompareOp::Gt => x > y,
+        CompareOp::Ge => x >= y,
+    };
+    Ok(Value::Bool(result))
+}
+
 pub struct ExprParser<'a> {
-    input: Vec<char>,
+    tokens: &'a [Token],
     pos: usize,
     variables: &'a HashMap<String, Value>,
     last_token_was_operator: bool,
+    /// Tracks whether the token just consumed was itself a unary `+`/`-`,
+    /// so a second one directly after it can be rejected. Reset to `false`
+    /// whenever a binary operator or `(` is consumed, so a single unary
+    /// sign is always allowed right after those (e.g. `1 & -2`), and it
+    /// never leaks across a parenthesized sub-expression.
+    last_token_was_unary: bool,
 }
 
 impl<'a> ExprParser<'a> {
-    pub fn new(expression: &str, variables: &'a HashMap<String, Value>) -> Self {
+    pub fn new(tokens: &'a [Token], pos: usize, variables: &'a HashMap<String, Value>) -> Self {
         Self {
-            input: expression.chars().collect(),
-            pos: 0,
+            tokens,
+            pos,
             variables,
             last_token_was_operator: true,
+            last_token_was_unary: false,
         }
     }
 
-    pub fn parse(&mut self) -> Result<Value, String> {
-        self.skip_whitespace();
-        let value = if self.peek() == Some('"') {
-            self.parse_string_literal()
-        } else {
-            self.parse_expression().map(Value::Int)
-        }?;
+    pub fn parse(&mut self) -> Result<Value, ParseError> {
+        let value = self.parse_comparison()?;
 
-        self.skip_whitespace();
-        if self.pos < self.input.len() {
-            return Err("Unexpected characters at end of input".to_string());
+        if self.pos < self.tokens.len() {
+            return Err(self.error("Unexpected characters at end of input"));
         }
 
         Ok(value)
     }
 
-    fn parse_expression(&mut self) -> Result<i32, String> {
-        let mut value = self.parse_term()?;
+    fn parse_comparison(&mut self) -> Result<Value, ParseError> {
+        let mut value = self.parse_bitor()?;
         loop {
-            self.skip_whitespace();
-            match self.peek() {
-                Some('+') => {
+            let op = match self.peek_kind() {
+                Some(TokenKind::Eq) => CompareOp::Eq,
+                Some(TokenKind::Ne) => CompareOp::Ne,
+                Some(TokenKind::Lt) => CompareOp::Lt,
+                Some(TokenKind::Le) => CompareOp::Le,
+                Some(TokenKind::Gt) => CompareOp::Gt,
+                Some(TokenKind::Ge) => CompareOp::Ge,
+                _ => break,
+            };
+            if self.last_token_was_operator {
+                return Err(self.error("Not allowed to have consecutive comparison operators"));
+            }
+            self.next();
+            self.last_token_was_operator = true;
+            self.last_token_was_unary = false;
+            let (line, col) = self.current_position();
+            let rhs = self.parse_bitor()?;
+            value = compare(value, rhs, op).map_err(|message| ParseError { message, line, col })?;
+            self.last_token_was_operator = false;
+        }
+        Ok(value)
+    }
+
+    fn parse_bitor(&mut self) -> Result<Value, ParseError> {
+        let mut value = self.parse_bitxor()?;
+        while let Some(&TokenKind::Pipe) = self.peek_kind() {
+            if self.last_token_was_operator {
+                return Err(self.error("Not allowed to have consecutive bitwise or (|) operators"));
+            }
+            self.next();
+            self.last_token_was_operator = true;
+            self.last_token_was_unary = false;
+            let (line, col) = self.current_position();
+            let rhs = self.parse_bitxor()?;
+            value = self.int_binop(line, col, value, rhs, "|", |a, b| a | b)?;
+            self.last_token_was_operator = false;
+        }
+        Ok(value)
+    }
+
+    fn parse_bitxor(&mut self) -> Result<Value, ParseError> {
+        let mut value = self.parse_bitand()?;
+        while let Some(&TokenKind::Caret) = self.peek_kind() {
+            if self.last_token_was_operator {
+                return Err(self.error("Not allowed to have consecutive bitwise xor (^) operators"));
+            }
+            self.next();
+            self.last_token_was_operator = true;
+            self.last_token_was_unary = false;
+            let (line, col) = self.current_position();
+            let rhs = self.parse_bitand()?;
+            value = self.int_binop(line, col, value, rhs, "^", |a, b| a ^ b)?;
+            self.last_token_was_operator = false;
+        }
+        Ok(value)
+    }
+
+    fn parse_bitand(&mut self) -> Result<Value, ParseError> {
+        let mut value = self.parse_shift()?;
+        while let Some(&TokenKind::Amp) = self.peek_kind() {
+            if self.last_token_was_operator {
+                return Err(self.error("Not allowed to have consecutive bitwise and (&) operators"));
+            }
+            self.next();
+            self.last_token_was_operator = true;
+            self.last_token_was_unary = false;
+            let (line, col) = self.current_position();
+            let rhs = self.parse_shift()?;
+            value = self.int_binop(line, col, value, rhs, "&", |a, b| a & b)?;
+            self.last_token_was_operator = false;
+        }
+        Ok(value)
+    }
+
+    fn parse_shift(&mut self) -> Result<Value, ParseError> {
+        let mut value = self.parse_expression()?;
+        loop {
+            match self.peek_kind() {
+                Some(TokenKind::Shl) => {
                     if self.last_token_was_operator {
                         return Err(
-                            "Not allowed to have consecutive addition (+) operators".to_string()
+                            self.error("Not allowed to have consecutive shift (<<) operators")
                         );
                     }
                     self.next();
                     self.last_token_was_operator = true;
-                    value += self.parse_term()?;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_expression()?;
+                    value = self.shift_binop(line, col, value, rhs, "<<", i32::checked_shl)?;
                 }
-                Some('-') => {
+                Some(TokenKind::Shr) => {
                     if self.last_token_was_operator {
                         return Err(
-                            "Not allowed to have consecutive subtraction (-) operators".to_string()
+                            self.error("Not allowed to have consecutive shift (>>) operators")
                         );
                     }
                     self.next();
                     self.last_token_was_operator = true;
-                    value -= self.parse_term()?;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_expression()?;
+                    value = self.shift_binop(line, col, value, rhs, ">>", i32::checked_shr)?;
                 }
                 _ => break,
             }
@@ -70,21 +288,90 @@ impl<'a> ExprParser<'a> {
         Ok(value)
     }
 
-    fn parse_term(&mut self) -> Result<i32, String> {
+    fn parse_expression(&mut self) -> Result<Value, ParseError> {
+        let mut value = self.parse_term()?;
+        loop {
+            match self.peek_kind() {
+                Some(TokenKind::Plus) => {
+                    if self.last_token_was_operator {
+                        return Err(self.error(
+                            "Not allowed to have consecutive addition (+) operators",
+                        ));
+                    }
+                    self.next();
+                    self.last_token_was_operator = true;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_term()?;
+                    value = numeric_binop(value, rhs, "+", i32::checked_add, |a, b| a + b)
+                        .map_err(|message| ParseError { message, line, col })?;
+                }
+                Some(TokenKind::Minus) => {
+                    if self.last_token_was_operator {
+                        return Err(self.error(
+                            "Not allowed to have consecutive subtraction (-) operators",
+                        ));
+                    }
+                    self.next();
+                    self.last_token_was_operator = true;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_term()?;
+                    value = numeric_binop(value, rhs, "-", i32::checked_sub, |a, b| a - b)
+                        .map_err(|message| ParseError { message, line, col })?;
+                }
+                _ => break,
+            }
+            self.last_token_was_operator = false;
+        }
+        Ok(value)
+    }
+
+    fn parse_term(&mut self) -> Result<Value, ParseError> {
         let mut value = self.parse_factor()?;
         loop {
-            self.skip_whitespace();
-            match self.peek() {
-                Some('*') => {
+            match self.peek_kind() {
+                Some(TokenKind::Star) => {
+                    if self.last_token_was_operator {
+                        return Err(self.error(
+                            "Not allowed to have consecutive multiplication (*) operators",
+                        ));
+                    }
+                    self.next();
+                    self.last_token_was_operator = true;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_factor()?;
+                    value = numeric_binop(value, rhs, "*", i32::checked_mul, |a, b| a * b)
+                        .map_err(|message| ParseError { message, line, col })?;
+                }
+                Some(TokenKind::Slash) => {
+                    if self.last_token_was_operator {
+                        return Err(
+                            self.error("Not allowed to have consecutive division (/) operators")
+                        );
+                    }
+                    self.next();
+                    self.last_token_was_operator = true;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_factor()?;
+                    value = divide(value, rhs)
+                        .map_err(|message| ParseError { message, line, col })?;
+                }
+                Some(TokenKind::Percent) => {
                     if self.last_token_was_operator {
                         return Err(
-                            "Not allowed to have consecutive multiplication (*) operators"
-                                .to_string(),
+                            self.error("Not allowed to have consecutive modulo (%) operators")
                         );
                     }
                     self.next();
                     self.last_token_was_operator = true;
-                    value *= self.parse_factor()?;
+                    self.last_token_was_unary = false;
+                    let (line, col) = self.current_position();
+                    let rhs = self.parse_factor()?;
+                    value = modulo(value, rhs)
+                        .map_err(|message| ParseError { message, line, col })?;
                 }
                 _ => break,
             }
@@ -93,106 +380,347 @@ impl<'a> ExprParser<'a> {
         Ok(value)
     }
 
-    fn parse_factor(&mut self) -> Result<i32, String> {
-        self.skip_whitespace();
-        match self.peek() {
-            Some('(') => {
+    fn parse_factor(&mut self) -> Result<Value, ParseError> {
+        match self.peek_kind() {
+            Some(TokenKind::LParen) => {
                 self.next();
-                let value = self.parse_expression()?;
-                self.skip_whitespace();
-                if self.next() != Some(')') {
-                    return Err("Expected ')' is missing".to_string());
+                self.last_token_was_unary = false;
+                let value = self.parse_comparison()?;
+                let unclosed = self.error("Expected ')' is missing");
+                if !matches!(self.next().map(|t| &t.kind), Some(TokenKind::RParen)) {
+                    return Err(unclosed);
                 }
                 Ok(value)
             }
-            Some('-') => {
-                if self.last_token_was_operator {
-                    return Err("Multiple unary (-) operators not allowed".to_string());
+            Some(TokenKind::Minus) => {
+                if self.last_token_was_unary {
+                    return Err(self.error("Multiple unary (-) operators not allowed"));
                 }
-                self.last_token_was_operator = true;
+                self.last_token_was_unary = true;
                 self.next();
-                Ok(-self.parse_factor()?)
+                let (line, col) = self.current_position();
+                let value = self.parse_factor()?;
+                negate(value).map_err(|message| ParseError { message, line, col })
             }
-            Some('+') => {
-                if self.last_token_was_operator {
-                    return Err("Multiple unary (+) operators not allowed".to_string());
+            Some(TokenKind::Plus) => {
+                if self.last_token_was_unary {
+                    return Err(self.error("Multiple unary (+) operators not allowed"));
                 }
-                self.last_token_was_operator = true;
+                self.last_token_was_unary = true;
                 self.next();
-                self.parse_factor()
+                let (line, col) = self.current_position();
+                match self.parse_factor()? {
+                    value @ (Value::Int(_) | Value::Float(_)) => Ok(value),
+                    other => Err(ParseError {
+                        message: format!("Cannot apply unary (+) to a {}", type_name(&other)),
+                        line,
+                        col,
+                    }),
+                }
             }
-            Some(c) if c.is_ascii_digit() => {
+            Some(TokenKind::Int) => {
                 self.last_token_was_operator = false;
                 self.parse_integer_literal()
             }
-            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            Some(TokenKind::Str) => {
+                self.last_token_was_operator = false;
+                self.parse_string_literal()
+            }
+            Some(TokenKind::Ident) => {
                 self.last_token_was_operator = false;
                 self.parse_identifier()
             }
-            _ => Err("Invalid token in expression".to_string()),
+            _ => Err(self.error("Invalid token in expression")),
         }
     }
 
-    fn parse_integer_literal(&mut self) -> Result<i32, String> {
-        let start = self.pos;
-        while self.peek().map_or(false, |c| c.is_ascii_digit()) {
-            self.next();
+    fn parse_integer_literal(&mut self) -> Result<Value, ParseError> {
+        let token = self.next().expect("caller checked for an Int token");
+        let (line, col) = (token.line as u16, token.col);
+        let text = token.text.as_str();
+
+        let (radix, digits) = if let Some(rest) = strip_base_prefix(text, "0x", "0X") {
+            (16, rest)
+        } else if let Some(rest) = strip_base_prefix(text, "0b", "0B") {
+            (2, rest)
+        } else if let Some(rest) = strip_base_prefix(text, "0o", "0O") {
+            (8, rest)
+        } else {
+            (10, text)
+        };
+
+        if radix == 10 {
+            if text.contains('.') || text.contains('e') || text.contains('E') {
+                return text.parse::<f64>().map(Value::Float).map_err(|_| ParseError {
+                    message: "Invalid number format".to_string(),
+                    line,
+                    col,
+                });
+            }
+            if digits.len() > 1 && digits.starts_with('0') {
+                return Err(ParseError {
+                    message: "Invalid number: leading zeros are not allowed".to_string(),
+                    line,
+                    col,
+                });
+            }
+            return digits.parse::<i32>().map(Value::Int).map_err(|_| ParseError {
+                message: "Invalid number format".to_string(),
+                line,
+                col,
+            });
         }
-        let number: String = self.input[start..self.pos].iter().collect();
-        if number.len() > 1 && number.starts_with('0') {
-            return Err("Invalid number: leading zeros are not allowed".to_string());
+
+        let digits: String = digits.chars().filter(|&c| c != '_').collect();
+        if digits.is_empty() {
+            return Err(ParseError {
+                message: format!("Invalid number: '{}' has no digits after its base prefix", text),
+                line,
+                col,
+            });
         }
-        number
-            .parse::<i32>()
-            .map_err(|_| "Invalid number format".to_string())
+        i32::from_str_radix(&digits, radix)
+            .map(Value::Int)
+            .map_err(|_| ParseError {
+                message: format!(
+                    "Invalid number: '{}' is not a valid base-{} literal",
+                    text, radix
+                ),
+                line,
+                col,
+            })
     }
 
-    fn parse_string_literal(&mut self) -> Result<Value, String> {
-        self.next();
-        let mut result = String::new();
-        while let Some(c) = self.peek() {
-            if c == '"' {
-                self.next();
-                return Ok(Value::Str(result));
-            }
-            result.push(c);
-            self.next();
+    fn parse_string_literal(&mut self) -> Result<Value, ParseError> {
+        let token = self.next().expect("caller checked for a Str token");
+        let (line, col) = (token.line as u16, token.col);
+        let text = unescape(&token.text).map_err(|message| ParseError { message, line, col })?;
+        Ok(Value::Str(text))
+    }
+
+    fn parse_identifier(&mut self) -> Result<Value, ParseError> {
+        let token = self.next().expect("caller checked for an Ident token");
+        let (line, col) = (token.line as u16, token.col);
+        let name = &token.text;
+        match self.variables.get(name) {
+            Some(value @ (Value::Int(_) | Value::Float(_) | Value::Bool(_))) => Ok(value.clone()),
+            Some(Value::Str(_)) => Err(ParseError {
+                message: format!("Cannot use string variable '{}' in arithmetic", name),
+                line,
+                col,
+            }),
+            None => Err(ParseError {
+                message: format!("Variable '{}' not defined", name),
+                line,
+                col,
+            }),
         }
-        Err("Unterminated string literal".to_string())
     }
 
-    fn parse_identifier(&mut self) -> Result<i32, String> {
-        let start = self.pos;
-        while self
-            .peek()
-            .map_or(false, |c| c.is_ascii_alphanumeric() || c == '_')
-        {
-            self.next();
+    fn int_binop(
+        &self,
+        line: u16,
+        col: u16,
+        a: Value,
+        b: Value,
+        op_name: &str,
+        op: impl Fn(i32, i32) -> i32,
+    ) -> Result<Value, ParseError> {
+        let mismatch = || ParseError {
+            message: format!(
+                "Cannot apply ({}) to a {} and a {}",
+                op_name,
+                type_name(&a),
+                type_name(&b)
+            ),
+            line,
+            col,
+        };
+        let x = as_int(&a).map_err(|_| mismatch())?;
+        let y = as_int(&b).map_err(|_| mismatch())?;
+        Ok(Value::Int(op(x, y)))
+    }
+
+    /// Like `int_binop`, but for `<<`/`>>`: the shift amount is cast to
+    /// `u32` and run through `checked_shl`/`checked_shr`, which reject any
+    /// amount outside `0..32` (including negative amounts, which wrap to a
+    /// huge `u32`) instead of panicking or producing a garbage result.
+    fn shift_binop(
+        &self,
+        line: u16,
+        col: u16,
+        a: Value,
+        b: Value,
+        op_name: &str,
+        shift: impl Fn(i32, u32) -> Option<i32>,
+    ) -> Result<Value, ParseError> {
+        let mismatch = || ParseError {
+            message: format!(
+                "Cannot apply ({}) to a {} and a {}",
+                op_name,
+                type_name(&a),
+                type_name(&b)
+            ),
+            line,
+            col,
+        };
+        let x = as_int(&a).map_err(|_| mismatch())?;
+        let y = as_int(&b).map_err(|_| mismatch())?;
+        shift(x, y as u32).map(Value::Int).ok_or_else(|| ParseError {
+            message: format!("Shift amount {} is out of range (expected 0..32)", y),
+            line,
+            col,
+        })
+    }
+
+    fn peek_kind(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn current_position(&self) -> (u16, u16) {
+        match self.tokens.get(self.pos) {
+            Some(token) => (token.line as u16, token.col),
+            None => match self.tokens.last() {
+                Some(token) => {
+                    // A Str token's text excludes its two quotes.
+                    let quotes = if token.kind == TokenKind::Str { 2 } else { 0 };
+                    let width = token.text.chars().count() + quotes;
+                    (token.line as u16, token.col.saturating_add(width as u16))
+                }
+                None => (1, 1),
+            },
         }
-        let name: String = self.input[start..self.pos].iter().collect();
-        match self.variables.get(&name) {
-            Some(Value::Int(n)) => Ok(*n),
-            Some(Value::Str(_)) => Err(format!(
-                "Cannot use string variable '{}' in arithmetic",
-                name
-            )),
-            None => Err(format!("Variable '{}' not defined", name)),
+    }
+
+    /// Builds a `ParseError` positioned at the token the cursor currently
+    /// sits on, or just past the last token when the cursor has run off
+    /// the end of the input (e.g. an unterminated expression).
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let (line, col) = self.current_position();
+        ParseError {
+            message: message.into(),
+            line,
+            col,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::tokenize;
+
+    fn eval(expr: &str) -> Result<Value, ParseError> {
+        let tokens = tokenize(expr);
+        let variables = HashMap::new();
+        ExprParser::new(&tokens, 0, &variables).parse()
+    }
 
-    fn peek(&self) -> Option<char> {
-        self.input.get(self.pos).copied()
+    fn parse_int(text: &str) -> Result<Value, ParseError> {
+        let tokens = vec![Token {
+            kind: TokenKind::Int,
+            line: 1,
+            col: 1,
+            text: text.to_string(),
+        }];
+        let variables = HashMap::new();
+        ExprParser::new(&tokens, 0, &variables).parse_integer_literal()
     }
 
-    fn next(&mut self) -> Option<char> {
-        let ch = self.peek();
-        self.pos += ch.is_some() as usize;
-        ch
+    #[test]
+    fn parse_integer_literal_hex() {
+        assert_eq!(parse_int("0xFF").unwrap(), Value::Int(255));
     }
 
-    fn skip_whitespace(&mut self) {
-        while self.peek().map_or(false, |c| c.is_whitespace()) {
-            self.next();
-        }
+    #[test]
+    fn parse_integer_literal_bin() {
+        assert_eq!(parse_int("0b101").unwrap(), Value::Int(5));
+    }
+
+    #[test]
+    fn parse_integer_literal_octal() {
+        assert_eq!(parse_int("0o17").unwrap(), Value::Int(15));
+    }
+
+    #[test]
+    fn parse_integer_literal_bad_digit() {
+        assert!(parse_int("0b102").is_err());
+    }
+
+    #[test]
+    fn parse_integer_literal_empty_digits() {
+        assert!(parse_int("0x").is_err());
+    }
+
+    #[test]
+    fn parse_integer_literal_leading_zero_decimal() {
+        assert!(parse_int("007").is_err());
+    }
+
+    #[test]
+    fn addition_overflows_cleanly() {
+        assert!(eval("2147483647 + 1").is_err());
+    }
+
+    #[test]
+    fn multiplication_overflows_cleanly() {
+        assert!(eval("2000000000 * 2000000000").is_err());
+    }
+
+    #[test]
+    fn division_by_zero_errors() {
+        assert!(eval("1 / 0").is_err());
+    }
+
+    #[test]
+    fn modulo_by_zero_errors() {
+        assert!(eval("1 % 0").is_err());
+    }
+
+    #[test]
+    fn shift_out_of_range_errors() {
+        assert!(eval("1 << 32").is_err());
+    }
+
+    #[test]
+    fn operator_precedence() {
+        assert_eq!(eval("2 + 3 * 4").unwrap(), Value::Int(14));
+    }
+
+    #[test]
+    fn unary_minus_after_binary_operator_is_allowed() {
+        assert_eq!(eval("1 & -2").unwrap(), Value::Int(0));
+        assert_eq!(eval("1 | -1").unwrap(), Value::Int(-1));
+        assert_eq!(eval("1 - -2").unwrap(), Value::Int(3));
+        assert_eq!(eval("1 < -2").unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn double_unary_minus_is_rejected() {
+        assert!(eval("- -2").is_err());
+    }
+
+    #[test]
+    fn parenthesized_unary_is_not_a_double_unary() {
+        assert_eq!(eval("-(-2)").unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    fn string_comparison_errors_instead_of_silently_failing() {
+        let err = eval("\"a\" < \"b\"").unwrap_err();
+        assert!(err.message.contains("Cannot compare"));
+    }
+
+    #[test]
+    fn plain_string_literal_parses() {
+        assert_eq!(eval("\"hello\"").unwrap(), Value::Str("hello".to_string()));
     }
 }
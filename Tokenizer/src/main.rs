@@ -1,10 +1,43 @@
 mod exp_parser;
+mod tokenizer;
+mod unescape;
 
-use exp_parser::{ExprParser, Value};
+use exp_parser::{ExprParser, ParseError, Value};
 use std::collections::HashMap;
 use std::io::{self, Write};
+use std::process::ExitCode;
+use tokenizer::{tokenize, Token, TokenKind};
 
-fn main() {
+struct Args {
+    path: Option<String>,
+    quiet: bool,
+}
+
+fn main() -> ExitCode {
+    let args = parse_args(std::env::args().skip(1));
+
+    match args.path {
+        Some(path) => run_file(&path, args.quiet),
+        None => {
+            run_repl();
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn parse_args(raw: impl Iterator<Item = String>) -> Args {
+    let mut path = None;
+    let mut quiet = false;
+    for arg in raw {
+        match arg.as_str() {
+            "--quiet" | "-q" => quiet = true,
+            _ => path = Some(arg),
+        }
+    }
+    Args { path, quiet }
+}
+
+fn run_repl() {
     let mut variables: HashMap<String, Value> = HashMap::new();
     println!("Enter your program or type exit to quit: ");
 
@@ -34,56 +67,115 @@ fn main() {
             continue;
         }
 
-        let statements: Vec<&str> = input
-            .split(';')
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        for statement in statements {
-            let parts: Vec<&str> = statement.split('=').map(str::trim).collect();
-            if parts.len() != 2 {
-                eprintln!(
-                    "error: invalid assignment format. Use the following format `name = value;`"
-                );
-                continue;
+        let tokens = tokenize(input);
+        for statement in split_statements(&tokens) {
+            if let Err(err) = run_statement(statement, &mut variables) {
+                eprintln!("error: {}", err);
             }
+        }
 
-            let var_name = parts[0];
-            if !is_valid_identifier(var_name) {
-                eprintln!("error: invalid identifier '{}'", var_name);
-                continue;
-            }
+        print_variables(&variables);
+    }
+}
 
-            let expr = parts[1];
-            let mut parser = ExprParser::new(expr, &variables);
-
-            match parser.parse() {
-                Ok(value) => {
-                    variables.insert(var_name.to_string(), value);
-                }
-                Err(err) => {
-                    eprintln!("error: {}", err);
-                    continue;
-                }
-            }
+fn run_file(path: &str, quiet: bool) -> ExitCode {
+    let program = match std::fs::read_to_string(path) {
+        Ok(program) => program,
+        Err(err) => {
+            eprintln!("error: failed to read '{}': {}", path, err);
+            return ExitCode::FAILURE;
         }
+    };
 
-        for (name, value) in &variables {
-            match value {
-                Value::Int(n) => println!("{} = {}", name, n),
-                Value::Str(s) => println!("{} = \"{}\"", name, s),
-            }
+    let mut variables: HashMap<String, Value> = HashMap::new();
+    let mut had_error = false;
+
+    let tokens = tokenize(&program);
+    for statement in split_statements(&tokens) {
+        if let Err(err) = run_statement(statement, &mut variables) {
+            eprintln!("error: {}", err);
+            had_error = true;
         }
     }
+
+    if !quiet {
+        print_variables(&variables);
+    }
+
+    if had_error {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
-fn is_valid_identifier(name: &str) -> bool {
-    let mut chars = name.chars();
-    match chars.next() {
-        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
-            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+fn print_variables(variables: &HashMap<String, Value>) {
+    for (name, value) in variables {
+        match value {
+            Value::Int(n) => println!("{} = {}", name, n),
+            Value::Float(f) => println!("{} = {}", name, format_float(*f)),
+            Value::Bool(b) => println!("{} = {}", name, b),
+            Value::Str(s) => println!("{} = \"{}\"", name, s),
         }
-        _ => false,
+    }
+}
+
+/// Formats a finite float with at least one decimal digit, so e.g. `3.0`
+/// doesn't come out looking like the integer `3`. Infinities and NaN use
+/// Rust's default `inf`/`-inf`/`NaN` text.
+fn format_float(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 {
+        format!("{:.1}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn split_statements(tokens: &[Token]) -> Vec<&[Token]> {
+    tokens
+        .split(|t| t.kind == TokenKind::Semicolon)
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+fn run_statement(
+    statement: &[Token],
+    variables: &mut HashMap<String, Value>,
+) -> Result<(), ParseError> {
+    if let Some(token) = statement.iter().find(|t| matches!(t.kind, TokenKind::Error(_))) {
+        return Err(lex_error(token));
+    }
+
+    match statement {
+        [name, assign, ..] if name.kind == TokenKind::Ident && assign.kind == TokenKind::Assign => {
+            let mut parser = ExprParser::new(statement, 2, variables);
+            let value = parser.parse()?;
+            variables.insert(name.text.clone(), value);
+            Ok(())
+        }
+        _ => {
+            let (line, col) = statement
+                .first()
+                .map(|t| (t.line as u16, t.col))
+                .unwrap_or((1, 1));
+            Err(ParseError {
+                message: "invalid assignment format. Use the following format `name = value;`"
+                    .to_string(),
+                line,
+                col,
+            })
+        }
+    }
+}
+
+fn lex_error(token: &Token) -> ParseError {
+    let message = match &token.kind {
+        TokenKind::Error(message) => message.clone(),
+        _ => unreachable!("caller only passes Error tokens"),
+    };
+    ParseError {
+        message,
+        line: token.line as u16,
+        col: token.col,
     }
 }
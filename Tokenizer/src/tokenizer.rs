@@ -0,0 +1,227 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// An integer or floating-point literal; the parser tells them apart by
+    /// the presence of a base prefix, a `.`, or an exponent in `text`.
+    Int,
+    Str,
+    Ident,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Amp,
+    Pipe,
+    Caret,
+    Shl,
+    Shr,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    LParen,
+    RParen,
+    Assign,
+    Semicolon,
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub line: u32,
+    pub col: u16,
+    pub text: String,
+}
+
+/// Turns raw source text into a flat list of positional tokens. Malformed
+/// input (an unterminated string, an unrecognized character) is stored as
+/// an `Error` token rather than aborting the scan, so the parser can decide
+/// how to report it.
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0usize;
+    let mut line: u32 = 1;
+    let mut col: u16 = 1;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+
+        if c == '\n' {
+            pos += 1;
+            line += 1;
+            col = 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            pos += 1;
+            col += 1;
+            continue;
+        }
+
+        let start_line = line;
+        let start_col = col;
+
+        if c.is_ascii_digit() {
+            let start = pos;
+            pos += 1;
+            col += 1;
+
+            let has_base_prefix = c == '0'
+                && pos < chars.len()
+                && matches!(chars[pos], 'x' | 'X' | 'b' | 'B' | 'o' | 'O');
+            if has_base_prefix {
+                pos += 1;
+                col += 1;
+                // The base alphabet is validated during parsing; the lexer
+                // just takes the maximal run of digit-like characters so
+                // e.g. a bare `0x` still lexes as one Int token instead of
+                // being split apart.
+                while pos < chars.len()
+                    && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_')
+                {
+                    pos += 1;
+                    col += 1;
+                }
+            } else {
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    pos += 1;
+                    col += 1;
+                }
+                // Fractional part: only consume the `.` if it is actually
+                // followed by a digit, so e.g. a trailing `;` isn't eaten.
+                if chars.get(pos) == Some(&'.') && chars.get(pos + 1).is_some_and(char::is_ascii_digit) {
+                    pos += 1;
+                    col += 1;
+                    while pos < chars.len() && chars[pos].is_ascii_digit() {
+                        pos += 1;
+                        col += 1;
+                    }
+                }
+                // Exponent: `e`/`E`, an optional sign, then at least one digit.
+                if matches!(chars.get(pos), Some('e') | Some('E')) {
+                    let mut exponent_end = pos + 1;
+                    if matches!(chars.get(exponent_end), Some('+') | Some('-')) {
+                        exponent_end += 1;
+                    }
+                    if chars.get(exponent_end).is_some_and(char::is_ascii_digit) {
+                        while pos < exponent_end {
+                            pos += 1;
+                            col += 1;
+                        }
+                        while pos < chars.len() && chars[pos].is_ascii_digit() {
+                            pos += 1;
+                            col += 1;
+                        }
+                    }
+                }
+            }
+            let text: String = chars[start..pos].iter().collect();
+            tokens.push(Token {
+                kind: TokenKind::Int,
+                line: start_line,
+                col: start_col,
+                text,
+            });
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+                col += 1;
+            }
+            let text: String = chars[start..pos].iter().collect();
+            tokens.push(Token {
+                kind: TokenKind::Ident,
+                line: start_line,
+                col: start_col,
+                text,
+            });
+            continue;
+        }
+
+        if c == '"' {
+            pos += 1;
+            col += 1;
+            let start = pos;
+            let mut terminated = false;
+            while pos < chars.len() {
+                if chars[pos] == '"' {
+                    terminated = true;
+                    break;
+                }
+                if chars[pos] == '\n' {
+                    break;
+                }
+                if chars[pos] == '\\' && pos + 1 < chars.len() {
+                    // Don't let an escaped quote (`\"`) end the literal early;
+                    // the escape itself is decoded later, once lexing is done.
+                    pos += 1;
+                    col += 1;
+                }
+                pos += 1;
+                col += 1;
+            }
+            let text: String = chars[start..pos].iter().collect();
+            if terminated {
+                pos += 1;
+                col += 1;
+                tokens.push(Token {
+                    kind: TokenKind::Str,
+                    line: start_line,
+                    col: start_col,
+                    text,
+                });
+            } else {
+                tokens.push(Token {
+                    kind: TokenKind::Error("Unterminated string literal".to_string()),
+                    line: start_line,
+                    col: start_col,
+                    text,
+                });
+            }
+            continue;
+        }
+
+        let next_c = chars.get(pos + 1).copied();
+        let (kind, width) = match (c, next_c) {
+            ('<', Some('<')) => (TokenKind::Shl, 2),
+            ('>', Some('>')) => (TokenKind::Shr, 2),
+            ('=', Some('=')) => (TokenKind::Eq, 2),
+            ('!', Some('=')) => (TokenKind::Ne, 2),
+            ('<', Some('=')) => (TokenKind::Le, 2),
+            ('>', Some('=')) => (TokenKind::Ge, 2),
+            ('+', _) => (TokenKind::Plus, 1),
+            ('-', _) => (TokenKind::Minus, 1),
+            ('*', _) => (TokenKind::Star, 1),
+            ('/', _) => (TokenKind::Slash, 1),
+            ('%', _) => (TokenKind::Percent, 1),
+            ('&', _) => (TokenKind::Amp, 1),
+            ('|', _) => (TokenKind::Pipe, 1),
+            ('^', _) => (TokenKind::Caret, 1),
+            ('<', _) => (TokenKind::Lt, 1),
+            ('>', _) => (TokenKind::Gt, 1),
+            ('(', _) => (TokenKind::LParen, 1),
+            (')', _) => (TokenKind::RParen, 1),
+            ('=', _) => (TokenKind::Assign, 1),
+            (';', _) => (TokenKind::Semicolon, 1),
+            (other, _) => (TokenKind::Error(format!("Invalid character '{}'", other)), 1),
+        };
+        let text: String = chars[pos..pos + width].iter().collect();
+        tokens.push(Token {
+            kind,
+            line: start_line,
+            col: start_col,
+            text,
+        });
+        pos += width;
+        col += width as u16;
+    }
+
+    tokens
+}
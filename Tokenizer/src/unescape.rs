@@ -0,0 +1,131 @@
+/// Decodes backslash escape sequences in the raw text between a pair of
+/// string-literal quotes, as captured by the tokenizer. Kept separate from
+/// both lexing and parsing so the escape grammar can be reused or tested on
+/// its own.
+pub fn unescape(raw: &str) -> Result<String, String> {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut result = String::with_capacity(raw.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '\\' {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        i += 1;
+        match chars.get(i) {
+            Some('n') => {
+                result.push('\n');
+                i += 1;
+            }
+            Some('t') => {
+                result.push('\t');
+                i += 1;
+            }
+            Some('r') => {
+                result.push('\r');
+                i += 1;
+            }
+            Some('\\') => {
+                result.push('\\');
+                i += 1;
+            }
+            Some('"') => {
+                result.push('"');
+                i += 1;
+            }
+            Some('0') => {
+                result.push('\0');
+                i += 1;
+            }
+            Some('u') => {
+                i += 1;
+                result.push(parse_unicode_escape(&chars, &mut i)?);
+            }
+            Some(other) => return Err(format!("Unknown escape sequence '\\{}'", other)),
+            None => return Err("Unterminated escape sequence".to_string()),
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_unicode_escape(chars: &[char], i: &mut usize) -> Result<char, String> {
+    if chars.get(*i) != Some(&'{') {
+        return Err("Expected '{' after \\u".to_string());
+    }
+    *i += 1;
+
+    let start = *i;
+    while chars.get(*i).is_some_and(|&c| c != '}') {
+        *i += 1;
+    }
+    if chars.get(*i) != Some(&'}') {
+        return Err("Unterminated \\u{...} escape".to_string());
+    }
+    let hex: String = chars[start..*i].iter().collect();
+    *i += 1;
+
+    let code = u32::from_str_radix(&hex, 16)
+        .map_err(|_| format!("Invalid \\u{{{}}} escape", hex))?;
+    char::from_u32(code).ok_or_else(|| format!("\\u{{{}}} is not a valid Unicode scalar value", hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_through_plain_text() {
+        assert_eq!(unescape("hello").unwrap(), "hello");
+    }
+
+    #[test]
+    fn decodes_newline_tab_and_carriage_return() {
+        assert_eq!(unescape(r"a\nb\tc\rd").unwrap(), "a\nb\tc\rd");
+    }
+
+    #[test]
+    fn decodes_backslash_and_quote() {
+        assert_eq!(unescape(r#"\\ \""#).unwrap(), "\\ \"");
+    }
+
+    #[test]
+    fn decodes_nul() {
+        assert_eq!(unescape(r"\0").unwrap(), "\0");
+    }
+
+    #[test]
+    fn decodes_unicode_escape() {
+        assert_eq!(unescape(r"\u{48}\u{65}\u{6C}\u{6C}\u{6F}").unwrap(), "Hello");
+    }
+
+    #[test]
+    fn unknown_escape_errors() {
+        let err = unescape(r"\q").unwrap_err();
+        assert!(err.contains("Unknown escape sequence"));
+    }
+
+    #[test]
+    fn unterminated_escape_errors() {
+        let err = unescape("\\").unwrap_err();
+        assert!(err.contains("Unterminated escape sequence"));
+    }
+
+    #[test]
+    fn unicode_escape_missing_brace_errors() {
+        assert!(unescape(r"\u48}").is_err());
+    }
+
+    #[test]
+    fn unicode_escape_unterminated_errors() {
+        assert!(unescape(r"\u{48").is_err());
+    }
+
+    #[test]
+    fn unicode_escape_out_of_range_errors() {
+        assert!(unescape(r"\u{FFFFFFFF}").is_err());
+    }
+}